@@ -0,0 +1,477 @@
+//! A concise text syntax for authoring policies, compiled directly into the
+//! existing [`Check`]/[`JsonControl`] representation so the JSON evaluator
+//! never has to know policies were written this way. For example:
+//!
+//! ```text
+//! control pkg_signed "image must be signed" {
+//!     exists(/image/signature) and /image/tag == "release"
+//! }
+//! ```
+//!
+//! Parse failures carry a line/column span and a caret-underlined snippet
+//! of the offending source line, rather than a generic error message.
+
+use std::fmt;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{Check, JsonControl};
+
+/// A single parse error, with the 1-based source location and a rendered
+/// snippet of the offending line so diagnostics are legible without the
+/// caller re-deriving them from a byte offset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DslError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+}
+
+impl fmt::Display for DslError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "  --> line {}, column {}", self.line, self.column)?;
+        writeln!(f, "   |")?;
+        writeln!(f, "{:>2} | {}", self.line, self.snippet)?;
+        write!(f, "   | {}^", " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+/// Compiles `source` (the policy DSL) into the same `Vec<JsonControl>` that
+/// `PolicyEngine::add_policies_from_json` consumes. The lexer and parser
+/// both stop at the first problem, so a malformed `source` yields a single
+/// `DslError` with a source-span diagnostic rather than an error list.
+pub fn parse_policy_dsl(source: &str) -> Result<Vec<JsonControl>, DslError> {
+    let tokens = Lexer::new(source).tokenize()?;
+    let mut parser = Parser::new(tokens, source);
+    parser.parse_program()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Control,
+    Exists,
+    Some,
+    Contains,
+    And,
+    Or,
+    Not,
+    If,
+    Then,
+    Else,
+    StartsWith,
+    EndsWith,
+    Matches,
+    True,
+    False,
+    Ident(String),
+    String(String),
+    Pointer(String),
+    Number(f64),
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+    EqEq,
+    NotEq,
+    Ge,
+    Le,
+    Gt,
+    Lt,
+    Eof,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pos {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    pos: Pos,
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    chars: Vec<char>,
+    idx: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Lexer {
+            source,
+            chars: source.chars().collect(),
+            idx: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>, pos: Pos) -> DslError {
+        make_error(self.source, message.into(), pos)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.idx).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.idx + offset).copied()
+    }
+
+    fn pos(&self) -> Pos {
+        Pos { line: self.line, column: self.column }
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.idx += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, DslError> {
+        let mut tokens = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments();
+            let pos = self.pos();
+            let Some(c) = self.peek() else {
+                tokens.push(Token { kind: TokenKind::Eof, pos });
+                break;
+            };
+
+            let kind = match c {
+                '{' => { self.advance(); TokenKind::LBrace }
+                '}' => { self.advance(); TokenKind::RBrace }
+                '(' => { self.advance(); TokenKind::LParen }
+                ')' => { self.advance(); TokenKind::RParen }
+                ',' => { self.advance(); TokenKind::Comma }
+                '=' if self.peek_at(1) == Some('=') => { self.advance(); self.advance(); TokenKind::EqEq }
+                '!' if self.peek_at(1) == Some('=') => { self.advance(); self.advance(); TokenKind::NotEq }
+                '>' if self.peek_at(1) == Some('=') => { self.advance(); self.advance(); TokenKind::Ge }
+                '<' if self.peek_at(1) == Some('=') => { self.advance(); self.advance(); TokenKind::Le }
+                '>' => { self.advance(); TokenKind::Gt }
+                '<' => { self.advance(); TokenKind::Lt }
+                '"' => self.lex_string(pos)?,
+                '/' => self.lex_pointer(),
+                c if c == '-' || c.is_ascii_digit() => self.lex_number(),
+                c if c.is_alphabetic() || c == '_' => self.lex_ident_or_keyword(),
+                other => {
+                    return Err(self.error(format!("unexpected character '{}'", other), pos));
+                }
+            };
+
+            tokens.push(Token { kind, pos });
+        }
+        Ok(tokens)
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            while self.peek().map_or(false, |c| c.is_whitespace()) {
+                self.advance();
+            }
+            if self.peek() == Some('#') {
+                while self.peek().map_or(false, |c| c != '\n') {
+                    self.advance();
+                }
+                continue;
+            }
+            break;
+        }
+    }
+
+    fn lex_string(&mut self, pos: Pos) -> Result<TokenKind, DslError> {
+        self.advance(); // opening quote
+        let mut s = String::new();
+        loop {
+            match self.advance() {
+                Some('"') => return Ok(TokenKind::String(s)),
+                Some('\\') => {
+                    match self.advance() {
+                        Some('"') => s.push('"'),
+                        Some('\\') => s.push('\\'),
+                        Some('n') => s.push('\n'),
+                        // Any other escape is left intact (backslash and all) so
+                        // regex patterns like `\d` or `\.` survive unmangled.
+                        Some(other) => { s.push('\\'); s.push(other); }
+                        None => return Err(self.error("unterminated string literal", pos)),
+                    }
+                }
+                Some(c) => s.push(c),
+                None => return Err(self.error("unterminated string literal", pos)),
+            }
+        }
+    }
+
+    fn lex_pointer(&mut self) -> TokenKind {
+        let mut s = String::new();
+        while self.peek().map_or(false, |c| c.is_alphanumeric() || matches!(c, '/' | '_' | '-' | '.')) {
+            s.push(self.advance().unwrap());
+        }
+        TokenKind::Pointer(s)
+    }
+
+    fn lex_number(&mut self) -> TokenKind {
+        let mut s = String::new();
+        if self.peek() == Some('-') {
+            s.push(self.advance().unwrap());
+        }
+        while self.peek().map_or(false, |c| c.is_ascii_digit() || c == '.') {
+            s.push(self.advance().unwrap());
+        }
+        TokenKind::Number(s.parse().unwrap_or(0.0))
+    }
+
+    fn lex_ident_or_keyword(&mut self) -> TokenKind {
+        let mut s = String::new();
+        while self.peek().map_or(false, |c| c.is_alphanumeric() || c == '_') {
+            s.push(self.advance().unwrap());
+        }
+        match s.as_str() {
+            "control" => TokenKind::Control,
+            "exists" => TokenKind::Exists,
+            "some" => TokenKind::Some,
+            "contains" => TokenKind::Contains,
+            "and" => TokenKind::And,
+            "or" => TokenKind::Or,
+            "not" => TokenKind::Not,
+            "if" => TokenKind::If,
+            "then" => TokenKind::Then,
+            "else" => TokenKind::Else,
+            "startsWith" => TokenKind::StartsWith,
+            "endsWith" => TokenKind::EndsWith,
+            "matches" => TokenKind::Matches,
+            "true" => TokenKind::True,
+            "false" => TokenKind::False,
+            _ => TokenKind::Ident(s),
+        }
+    }
+}
+
+fn make_error(source: &str, message: String, pos: Pos) -> DslError {
+    let snippet = source.lines().nth(pos.line.saturating_sub(1)).unwrap_or("").to_string();
+    DslError { message, line: pos.line, column: pos.column, snippet }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    idx: usize,
+    source_lines: Vec<String>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>, source: &str) -> Self {
+        let source_lines = source.lines().map(String::from).collect();
+        Parser { tokens, idx: 0, source_lines }
+    }
+
+    fn error(&self, message: impl Into<String>) -> DslError {
+        let pos = self.peek().pos;
+        let snippet = self.source_lines.get(pos.line.saturating_sub(1)).cloned().unwrap_or_default();
+        DslError { message: message.into(), line: pos.line, column: pos.column, snippet }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.idx]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.idx].clone();
+        if self.idx + 1 < self.tokens.len() {
+            self.idx += 1;
+        }
+        token
+    }
+
+    fn check(&self, kind: &TokenKind) -> bool {
+        std::mem::discriminant(&self.peek().kind) == std::mem::discriminant(kind)
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if self.check(kind) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind, what: &str) -> Result<Token, DslError> {
+        if self.check(&kind) {
+            Ok(self.advance())
+        } else {
+            Err(self.error(format!("expected {}, found {:?}", what, self.peek().kind)))
+        }
+    }
+
+    fn parse_program(&mut self) -> Result<Vec<JsonControl>, DslError> {
+        let mut controls = Vec::new();
+        while !self.check(&TokenKind::Eof) {
+            controls.push(self.parse_control()?);
+        }
+        Ok(controls)
+    }
+
+    fn parse_control(&mut self) -> Result<JsonControl, DslError> {
+        self.expect(TokenKind::Control, "'control'")?;
+        let id = match self.advance().kind {
+            TokenKind::Ident(name) => name,
+            other => return Err(self.error(format!("expected a control identifier, found {:?}", other))),
+        };
+        let description = match self.advance().kind {
+            TokenKind::String(s) => s,
+            other => return Err(self.error(format!("expected a quoted description, found {:?}", other))),
+        };
+        self.expect(TokenKind::LBrace, "'{'")?;
+        let check = self.parse_expr()?;
+        self.expect(TokenKind::RBrace, "'}'")?;
+        Ok(JsonControl { id, description, check })
+    }
+
+    fn parse_expr(&mut self) -> Result<Check, DslError> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Check, DslError> {
+        let mut rules = vec![self.parse_and()?];
+        while self.eat(&TokenKind::Or) {
+            rules.push(self.parse_and()?);
+        }
+        Ok(if rules.len() == 1 { rules.pop().unwrap() } else { Check::AnyOf { rules } })
+    }
+
+    fn parse_and(&mut self) -> Result<Check, DslError> {
+        let mut rules = vec![self.parse_unary()?];
+        while self.eat(&TokenKind::And) {
+            rules.push(self.parse_unary()?);
+        }
+        Ok(if rules.len() == 1 { rules.pop().unwrap() } else { Check::AllOf { rules } })
+    }
+
+    fn parse_unary(&mut self) -> Result<Check, DslError> {
+        if self.eat(&TokenKind::Not) {
+            let rule = self.parse_unary()?;
+            return Ok(Check::Not { rule: Box::new(rule) });
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Check, DslError> {
+        if self.eat(&TokenKind::If) {
+            let if_cond = self.parse_expr()?;
+            self.expect(TokenKind::Then, "'then'")?;
+            let then_cond = self.parse_expr()?;
+            let else_cond = if self.eat(&TokenKind::Else) {
+                Some(Box::new(self.parse_expr()?))
+            } else {
+                None
+            };
+            return Ok(Check::If {
+                if_cond: Box::new(if_cond),
+                then_cond: Box::new(then_cond),
+                else_cond,
+            });
+        }
+
+        if self.eat(&TokenKind::LParen) {
+            let inner = self.parse_expr()?;
+            self.expect(TokenKind::RParen, "')'")?;
+            return Ok(inner);
+        }
+
+        if self.eat(&TokenKind::Exists) {
+            self.expect(TokenKind::LParen, "'('")?;
+            let field = self.expect_pointer()?;
+            self.expect(TokenKind::RParen, "')'")?;
+            return Ok(Check::ExistsAndNotEmpty { field });
+        }
+
+        if self.eat(&TokenKind::Some) {
+            self.expect(TokenKind::LParen, "'('")?;
+            let field = self.expect_pointer()?;
+            self.expect(TokenKind::RParen, "')'")?;
+            return Ok(Check::Some { field });
+        }
+
+        if self.eat(&TokenKind::Contains) {
+            self.expect(TokenKind::LParen, "'('")?;
+            let field = self.expect_pointer()?;
+            self.expect(TokenKind::Comma, "','")?;
+            let value = self.parse_value()?;
+            self.expect(TokenKind::RParen, "')'")?;
+            return Ok(Check::Contains { field, value });
+        }
+
+        if matches!(self.peek().kind, TokenKind::Pointer(_)) {
+            let field = self.expect_pointer()?;
+            return self.parse_field_comparison(field);
+        }
+
+        Err(self.error(format!("expected an expression, found {:?}", self.peek().kind)))
+    }
+
+    fn parse_field_comparison(&mut self, field: String) -> Result<Check, DslError> {
+        match self.advance().kind {
+            TokenKind::EqEq => Ok(Check::Equals { field, value: self.parse_value()? }),
+            TokenKind::NotEq => Ok(Check::Not { rule: Box::new(Check::Equals { field, value: self.parse_value()? }) }),
+            TokenKind::Gt => Ok(Check::GreaterThan { field, value: self.expect_number()? }),
+            TokenKind::Lt => Ok(Check::LessThan { field, value: self.expect_number()? }),
+            TokenKind::Ge => Ok(Check::GreaterOrEqual { field, value: self.expect_number()? }),
+            TokenKind::Le => Ok(Check::LessOrEqual { field, value: self.expect_number()? }),
+            TokenKind::StartsWith => Ok(Check::StartsWith { field, value: self.expect_string()? }),
+            TokenKind::EndsWith => Ok(Check::EndsWith { field, value: self.expect_string()? }),
+            TokenKind::Matches => {
+                let pattern = self.expect_string()?;
+                if let Err(e) = Regex::new(&pattern) {
+                    return Err(self.error(format!("invalid regex pattern {:?}: {}", pattern, e)));
+                }
+                Ok(Check::Matches { field, pattern })
+            }
+            other => Err(self.error(format!("expected a comparison operator after '{}', found {:?}", field, other))),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, DslError> {
+        match self.advance().kind {
+            TokenKind::String(s) => Ok(Value::String(s)),
+            TokenKind::Number(n) => Ok(Value::from(n)),
+            TokenKind::True => Ok(Value::Bool(true)),
+            TokenKind::False => Ok(Value::Bool(false)),
+            other => Err(self.error(format!("expected a value, found {:?}", other))),
+        }
+    }
+
+    fn expect_pointer(&mut self) -> Result<String, DslError> {
+        match self.advance().kind {
+            TokenKind::Pointer(p) => Ok(p),
+            other => Err(self.error(format!("expected a JSON pointer (e.g. /image/tag), found {:?}", other))),
+        }
+    }
+
+    fn expect_string(&mut self) -> Result<String, DslError> {
+        match self.advance().kind {
+            TokenKind::String(s) => Ok(s),
+            other => Err(self.error(format!("expected a quoted string, found {:?}", other))),
+        }
+    }
+
+    fn expect_number(&mut self) -> Result<f64, DslError> {
+        match self.advance().kind {
+            TokenKind::Number(n) => Ok(n),
+            other => Err(self.error(format!("expected a number, found {:?}", other))),
+        }
+    }
+}
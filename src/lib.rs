@@ -1,12 +1,153 @@
+use std::collections::HashSet;
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 use schemars::{schema_for, JsonSchema};
+use regex::Regex;
+use thiserror::Error;
+
+mod dsl;
+pub use dsl::{parse_policy_dsl, DslError};
+
+/// Errors produced while loading or evaluating policies. Every variant
+/// carries enough context (the offending path, control id, or field) to
+/// report a useful message without the caller having to re-derive it.
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error("I/O error on {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse policy file {path:?}: {source}")]
+    Parse {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("duplicate control id {id:?}")]
+    DuplicateControlId { id: String },
+
+    #[error("failed to serialize validation report for {path:?}: {source}")]
+    Write {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("invalid JSON pointer {field:?} in control {control:?}")]
+    InvalidPointer { control: String, field: String },
+
+    #[error("failed to parse metadata file {path:?}: {source}")]
+    InvalidMetadata {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("policy backend failed to evaluate controls: {message}")]
+    Backend { message: String },
+}
 
 #[derive(Debug, Serialize)]
 pub struct Requirements {
     pub control: String,
     pub required: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FailureDetail>,
+}
+
+/// Describes the specific leaf check that caused a `Requirements` entry to
+/// fail: the JSON pointer that was evaluated, the operator that evaluated
+/// it, and the expected vs. actual values involved.
+#[derive(Debug, Serialize)]
+pub struct FailureDetail {
+    pub field: String,
+    pub operator: String,
+    pub expected: Value,
+    pub actual: Value,
+}
+
+impl fmt::Display for FailureDetail {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "field {} {}: expected {}, found {}", self.field, self.operator, self.expected, self.actual)
+    }
+}
+
+fn missing_value() -> Value {
+    Value::String("missing".to_string())
+}
+
+/// Best-effort JSON pointer for a check, used to label the synthetic
+/// failure produced when a `not` rule's inner check unexpectedly passes.
+/// Composite checks fall back to their first sub-rule's field.
+fn leaf_field(check: &Check) -> String {
+    match check {
+        Check::ExistsAndNotEmpty { field } => field.clone(),
+        Check::Equals { field, .. } => field.clone(),
+        Check::Contains { field, .. } => field.clone(),
+        Check::Some { field } => field.clone(),
+        Check::StartsWith { field, .. } => field.clone(),
+        Check::EndsWith { field, .. } => field.clone(),
+        Check::Matches { field, .. } => field.clone(),
+        Check::GreaterThan { field, .. } => field.clone(),
+        Check::LessThan { field, .. } => field.clone(),
+        Check::GreaterOrEqual { field, .. } => field.clone(),
+        Check::LessOrEqual { field, .. } => field.clone(),
+        Check::AllOf { rules } | Check::AnyOf { rules } => {
+            rules.first().map(leaf_field).unwrap_or_else(|| "<rule>".to_string())
+        }
+        Check::Not { rule } => leaf_field(rule),
+        Check::If { if_cond, .. } => leaf_field(if_cond),
+    }
+}
+
+/// Validates that every JSON pointer referenced by `check` is well-formed
+/// per RFC 6901 (empty, or starting with `/`), so a typo'd field surfaces
+/// as a `PolicyError::InvalidPointer` when the policy is loaded rather than
+/// silently failing every evaluation.
+fn validate_check_pointers(control_id: &str, check: &Check) -> Result<(), PolicyError> {
+    fn validate_pointer(control_id: &str, field: &str) -> Result<(), PolicyError> {
+        if field.is_empty() || field.starts_with('/') {
+            Ok(())
+        } else {
+            Err(PolicyError::InvalidPointer {
+                control: control_id.to_string(),
+                field: field.to_string(),
+            })
+        }
+    }
+
+    match check {
+        Check::ExistsAndNotEmpty { field }
+        | Check::Some { field }
+        | Check::Equals { field, .. }
+        | Check::Contains { field, .. }
+        | Check::StartsWith { field, .. }
+        | Check::EndsWith { field, .. }
+        | Check::Matches { field, .. }
+        | Check::GreaterThan { field, .. }
+        | Check::LessThan { field, .. }
+        | Check::GreaterOrEqual { field, .. }
+        | Check::LessOrEqual { field, .. } => validate_pointer(control_id, field),
+        Check::AllOf { rules } | Check::AnyOf { rules } => {
+            rules.iter().try_for_each(|rule| validate_check_pointers(control_id, rule))
+        }
+        Check::Not { rule } => validate_check_pointers(control_id, rule),
+        Check::If { if_cond, then_cond, else_cond } => {
+            validate_check_pointers(control_id, if_cond)?;
+            validate_check_pointers(control_id, then_cond)?;
+            if let Some(else_rule) = else_cond {
+                validate_check_pointers(control_id, else_rule)?;
+            }
+            Ok(())
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -20,16 +161,19 @@ struct PolicyFile {
     controls: Vec<JsonControl>,
 }
 
-#[derive(Deserialize, JsonSchema)]
-struct JsonControl {
-    id: String,
-    description: String,
-    check: Check,
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct JsonControl {
+    pub id: String,
+    pub description: String,
+    pub check: Check,
 }
 
-#[derive(Deserialize, JsonSchema)]
+/// The `Check` tree a control compiles to, shared by the JSON policy format,
+/// the [`crate::parse_policy_dsl`] DSL, and any external [`PolicyBackend`]
+/// that wants to interpret it directly instead of ignoring it.
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(tag = "op")]
-enum Check {
+pub enum Check {
     #[serde(rename = "exists_and_not_empty")]
     ExistsAndNotEmpty { field: String },
 
@@ -60,86 +204,366 @@ enum Check {
 
     #[serde(rename = "contains")]
     Contains { field: String, value: Value },
+
+    #[serde(rename = "startsWith")]
+    StartsWith { field: String, value: String },
+
+    #[serde(rename = "endsWith")]
+    EndsWith { field: String, value: String },
+
+    #[serde(rename = "matches")]
+    Matches {
+        field: String,
+        #[serde(deserialize_with = "deserialize_regex_pattern")]
+        pattern: String,
+    },
+
+    #[serde(rename = "greaterThan")]
+    GreaterThan { field: String, value: f64 },
+
+    #[serde(rename = "lessThan")]
+    LessThan { field: String, value: f64 },
+
+    #[serde(rename = "greaterOrEqual")]
+    GreaterOrEqual { field: String, value: f64 },
+
+    #[serde(rename = "lessOrEqual")]
+    LessOrEqual { field: String, value: f64 },
 }
 
-pub struct PolicyEngine {
-    controls: Vec<JsonControl>
+/// Validates that `pattern` compiles as a regex at policy-parse time, so a
+/// malformed `matches` rule is rejected when the policy file is loaded
+/// rather than silently failing every check at evaluation time.
+fn deserialize_regex_pattern<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(serde::de::Error::custom)?;
+    Ok(pattern)
 }
 
+pub struct PolicyEngine {
+    controls: Vec<JsonControl>,
+    backend: Box<dyn PolicyBackend>,
+}
 
 impl PolicyEngine {
+    /// Creates an engine using the built-in [`JsonCheckBackend`], which
+    /// interprets each control's `Check` tree directly.
     pub fn new() -> Self {
-        PolicyEngine { controls: Vec::new() }
+        PolicyEngine::with_backend(JsonCheckBackend)
     }
 
-    pub fn add_policies_from_json(&mut self, json_data: &str) -> Result<(), serde_json::Error> {
-        let policy_file: PolicyFile = serde_json::from_str(json_data)?;
+    /// Creates an engine that evaluates controls via `backend` instead of
+    /// the built-in `Check`-tree interpreter, e.g. to delegate to an
+    /// external policy evaluator.
+    pub fn with_backend(backend: impl PolicyBackend + 'static) -> Self {
+        PolicyEngine { controls: Vec::new(), backend: Box::new(backend) }
+    }
+
+    /// Parses `json_data` as a policy file and appends its controls.
+    /// `source_name` is only used to label `PolicyError::Parse` should the
+    /// data be malformed.
+    pub fn add_policies_from_json(&mut self, source_name: &str, json_data: &str) -> Result<(), PolicyError> {
+        let policy_file: PolicyFile = serde_json::from_str(json_data)
+            .map_err(|source| PolicyError::Parse { path: source_name.to_string(), source })?;
+
+        let mut seen_ids: HashSet<&str> = self.controls.iter().map(|c| c.id.as_str()).collect();
+        for control in &policy_file.controls {
+            if !seen_ids.insert(control.id.as_str()) {
+                return Err(PolicyError::DuplicateControlId { id: control.id.clone() });
+            }
+            validate_check_pointers(&control.id, &control.check)?;
+        }
+
         self.controls.extend(policy_file.controls);
         Ok(())
     }
 
-    pub fn validate(&self, metadata: &Value) -> Validation {
+    /// Loads every `*.json` file in `dir` as a policy file, in directory
+    /// order. A file that fails to read or parse is logged to stderr and
+    /// skipped, so one bad file doesn't take
+    /// down the rest of the batch; only a problem with `dir` itself (e.g.
+    /// it doesn't exist) fails the whole call.
+    pub fn add_policies_from_dir(&mut self, dir: impl AsRef<Path>) -> Result<(), PolicyError> {
+        let dir = dir.as_ref();
+        let entries = fs::read_dir(dir).map_err(|source| PolicyError::Io { path: dir.to_path_buf(), source })?;
+
+        for entry in entries {
+            let entry = entry.map_err(|source| PolicyError::Io { path: dir.to_path_buf(), source })?;
+            let path = entry.path();
+            if !path.is_file() || path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+
+            let source_name = path.file_name().unwrap().to_string_lossy().to_string();
+            let json_data = match fs::read_to_string(&path) {
+                Ok(data) => data,
+                Err(source) => {
+                    eprintln!("  - Error: {}", PolicyError::Io { path: path.clone(), source });
+                    continue;
+                }
+            };
+
+            if let Err(e) = self.add_policies_from_json(&source_name, &json_data) {
+                eprintln!("  - Error: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self, metadata: &Value) -> Result<Validation, PolicyError> {
+        self.backend.evaluate(&self.controls, metadata)
+    }
+}
+
+/// Evaluates a set of controls against metadata and produces a [`Validation`],
+/// or a [`PolicyError::Backend`] if the backend itself couldn't complete the
+/// evaluation (e.g. a process it shells out to failed to start, or returned a
+/// decision it couldn't make sense of). The built-in [`JsonCheckBackend`]
+/// interprets the `Check` tree directly; an alternative backend (e.g. one
+/// that shells out to a Rego evaluator) can be swapped in via
+/// [`PolicyEngine::with_backend`] as long as it maps its own decision into
+/// this same `Validation`/`Requirements` shape.
+pub trait PolicyBackend {
+    fn evaluate(&self, controls: &[JsonControl], metadata: &Value) -> Result<Validation, PolicyError>;
+}
+
+/// The default [`PolicyBackend`]: interprets each control's `Check` tree
+/// directly against the metadata document. Never fails on its own account.
+pub struct JsonCheckBackend;
+
+impl PolicyBackend for JsonCheckBackend {
+    fn evaluate(&self, controls: &[JsonControl], metadata: &Value) -> Result<Validation, PolicyError> {
         let mut failed_reqs = Vec::new();
 
-        for control in &self.controls {
-            if !self.run_check(&control.check, metadata) {
+        for control in controls {
+            let details = run_check(&control.check, metadata);
+            if !details.is_empty() {
                 failed_reqs.push(Requirements {
                     control: control.id.clone(),
                     required: control.description.clone(),
+                    details,
                 });
             }
         }
 
         if failed_reqs.is_empty() {
-            Validation::True
+            Ok(Validation::True)
         } else {
-            Validation::False(failed_reqs)
+            Ok(Validation::False(failed_reqs))
         }
     }
+}
 
-    fn run_check(&self, check: &Check, metadata: &Value) -> bool {
-        match check {
-            Check::ExistsAndNotEmpty { field }  => {
-                metadata.pointer(field)
-                    .and_then(|v| v.as_str())
-                    .map_or(false, |s| !s.is_empty())
+/// Evaluates `check` against `metadata`. Returns an empty vector when
+/// the check passes, or a vector containing the `FailureDetail` for the
+/// specific leaf check that failed otherwise.
+fn run_check(check: &Check, metadata: &Value) -> Vec<FailureDetail> {
+    match check {
+            Check::ExistsAndNotEmpty { field } => {
+                let found = metadata.pointer(field);
+                let passed = found.and_then(|v| v.as_str()).map_or(false, |s| !s.is_empty());
+                if passed {
+                    Vec::new()
+                } else {
+                    vec![FailureDetail {
+                        field: field.clone(),
+                        operator: "exists_and_not_empty".to_string(),
+                        expected: Value::String("non-empty string".to_string()),
+                        actual: found.cloned().unwrap_or_else(missing_value),
+                    }]
+                }
             }
-            Check::Equals {field, value} => {
-                metadata.pointer(field).map_or(false, |v| v == value)
+            Check::Equals { field, value } => {
+                let found = metadata.pointer(field);
+                if found.map_or(false, |v| v == value) {
+                    Vec::new()
+                } else {
+                    vec![FailureDetail {
+                        field: field.clone(),
+                        operator: "equals".to_string(),
+                        expected: value.clone(),
+                        actual: found.cloned().unwrap_or_else(missing_value),
+                    }]
+                }
             }
             Check::AllOf { rules } => {
-                rules.iter().all(|rule| self.run_check(rule, metadata))
+                for rule in rules {
+                    let details = run_check(rule, metadata);
+                    if !details.is_empty() {
+                        return details;
+                    }
+                }
+                Vec::new()
             }
             Check::AnyOf { rules } => {
-                rules.iter().any(|rule| self.run_check(rule, metadata))
+                // Reports whichever sub-rule failed last, not "the" reason
+                // the whole anyOf failed — with several failing branches
+                // this is an arbitrary pick, just the most recent one tried.
+                let mut last_failure = Vec::new();
+                for rule in rules {
+                    let details = run_check(rule, metadata);
+                    if details.is_empty() {
+                        return Vec::new();
+                    }
+                    last_failure = details;
+                }
+                last_failure
             }
             Check::Not { rule } => {
-                !self.run_check(rule, metadata)
+                if run_check(rule, metadata).is_empty() {
+                    vec![FailureDetail {
+                        field: leaf_field(rule),
+                        operator: "not".to_string(),
+                        expected: Value::String("rule to fail".to_string()),
+                        actual: Value::String("rule passed".to_string()),
+                    }]
+                } else {
+                    Vec::new()
+                }
             }
-            Check::Some { field} => {
-                metadata.pointer(field).map_or(false, |v| !v.is_null())
+            Check::Some { field } => {
+                let found = metadata.pointer(field);
+                if found.map_or(false, |v| !v.is_null()) {
+                    Vec::new()
+                } else {
+                    vec![FailureDetail {
+                        field: field.clone(),
+                        operator: "some".to_string(),
+                        expected: Value::String("present".to_string()),
+                        actual: found.cloned().unwrap_or_else(missing_value),
+                    }]
+                }
             }
-            Check::If { if_cond, then_cond, else_cond} => {
-                let if_result = self.run_check(if_cond, metadata);
+            Check::If { if_cond, then_cond, else_cond } => {
+                let if_passed = run_check(if_cond, metadata).is_empty();
 
                 match else_cond {
-                    None => !if_result || self.run_check(then_cond, metadata),
-
+                    None => {
+                        if if_passed {
+                            run_check(then_cond, metadata)
+                        } else {
+                            Vec::new()
+                        }
+                    }
                     Some(else_rule) => {
-                        if if_result {
-                            self.run_check(then_cond, metadata)
+                        if if_passed {
+                            run_check(then_cond, metadata)
                         } else {
-                            self.run_check(else_rule, metadata)
+                            run_check(else_rule, metadata)
                         }
                     }
                 }
             }
             Check::Contains { field, value } => {
-                metadata.pointer(field)
-                    .and_then(|v| v.as_array())
-                    .map_or(false, |arr| arr.contains(value))
+                let found = metadata.pointer(field);
+                let passed = found.and_then(|v| v.as_array()).map_or(false, |arr| arr.contains(value));
+                if passed {
+                    Vec::new()
+                } else {
+                    vec![FailureDetail {
+                        field: field.clone(),
+                        operator: "contains".to_string(),
+                        expected: value.clone(),
+                        actual: found.cloned().unwrap_or_else(missing_value),
+                    }]
+                }
+            }
+            Check::StartsWith { field, value } => {
+                let found = metadata.pointer(field);
+                let passed = found.and_then(|v| v.as_str()).map_or(false, |s| s.starts_with(value.as_str()));
+                if passed {
+                    Vec::new()
+                } else {
+                    vec![FailureDetail {
+                        field: field.clone(),
+                        operator: "startsWith".to_string(),
+                        expected: Value::String(value.clone()),
+                        actual: found.cloned().unwrap_or_else(missing_value),
+                    }]
+                }
+            }
+            Check::EndsWith { field, value } => {
+                let found = metadata.pointer(field);
+                let passed = found.and_then(|v| v.as_str()).map_or(false, |s| s.ends_with(value.as_str()));
+                if passed {
+                    Vec::new()
+                } else {
+                    vec![FailureDetail {
+                        field: field.clone(),
+                        operator: "endsWith".to_string(),
+                        expected: Value::String(value.clone()),
+                        actual: found.cloned().unwrap_or_else(missing_value),
+                    }]
+                }
+            }
+            Check::Matches { field, pattern } => {
+                // `pattern` is normally validated by `deserialize_regex_pattern` or the
+                // DSL parser before it ever reaches here, but `Check` is a public type
+                // so a hand-built `Check::Matches` can carry an uncompilable pattern.
+                // Treat that as a failed check rather than panicking on reachable input.
+                let regex = match Regex::new(pattern) {
+                    Ok(regex) => regex,
+                    Err(e) => {
+                        return vec![FailureDetail {
+                            field: field.clone(),
+                            operator: "matches".to_string(),
+                            expected: Value::String(format!("a valid regex: {}", e)),
+                            actual: Value::String(pattern.clone()),
+                        }];
+                    }
+                };
+                let found = metadata.pointer(field);
+                let passed = found.and_then(|v| v.as_str()).map_or(false, |s| regex.is_match(s));
+                if passed {
+                    Vec::new()
+                } else {
+                    vec![FailureDetail {
+                        field: field.clone(),
+                        operator: "matches".to_string(),
+                        expected: Value::String(pattern.clone()),
+                        actual: found.cloned().unwrap_or_else(missing_value),
+                    }]
+                }
+            }
+            Check::GreaterThan { field, value } => {
+                numeric_compare(field, *value, metadata, "greaterThan", |actual, expected| actual > expected)
+            }
+            Check::LessThan { field, value } => {
+                numeric_compare(field, *value, metadata, "lessThan", |actual, expected| actual < expected)
+            }
+            Check::GreaterOrEqual { field, value } => {
+                numeric_compare(field, *value, metadata, "greaterOrEqual", |actual, expected| actual >= expected)
+            }
+            Check::LessOrEqual { field, value } => {
+                numeric_compare(field, *value, metadata, "lessOrEqual", |actual, expected| actual <= expected)
             }
         }
+}
+
+/// Shared implementation for the numeric comparison operators: coerces
+/// the pointed-to JSON value to `f64` and applies `cmp(actual, expected)`.
+fn numeric_compare(
+    field: &str,
+    expected: f64,
+    metadata: &Value,
+    operator: &str,
+    cmp: impl Fn(f64, f64) -> bool,
+) -> Vec<FailureDetail> {
+    let found = metadata.pointer(field);
+    let passed = found.and_then(|v| v.as_f64()).map_or(false, |actual| cmp(actual, expected));
+    if passed {
+        Vec::new()
+    } else {
+        vec![FailureDetail {
+            field: field.to_string(),
+            operator: operator.to_string(),
+            expected: Value::from(expected),
+            actual: found.cloned().unwrap_or_else(missing_value),
+        }]
     }
 }
 
@@ -179,6 +603,9 @@ impl fmt::Display for Validation {
                 writeln!(f, "Validation FAILED with {} {}:", reqs.len(), plural)?;
                 for (i, r) in reqs.iter().enumerate() {
                     writeln!(f, "  - {}: RULE \"{}\" => {}", i+1, r.control, r.required)?;
+                    for d in &r.details {
+                        writeln!(f, "      {}", d)?;
+                    }
                 }
                 Ok(())
             }
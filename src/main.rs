@@ -1,10 +1,10 @@
-use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use serde_json::Value;
 use clap::Parser;
 
-use policy_engine::{PolicyEngine};
+use policy_engine::{PolicyEngine, PolicyError};
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -21,69 +21,52 @@ struct Args {
     output: Option<String>,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
+fn run(args: &Args) -> Result<(), PolicyError> {
     let mut engine = PolicyEngine::new();
-    
-    let args = Args::parse();
 
     let policies_path = Path::new(&args.policies);
+    let canonical_policies_path = policies_path
+        .canonicalize()
+        .map_err(|source| PolicyError::Io { path: policies_path.to_path_buf(), source })?;
+    println!("Loading policies from {:?}...", canonical_policies_path);
 
-    println!("Loading policies from {:?}...", policies_path.canonicalize()?);
-
-    for entry in fs::read_dir(policies_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        
-        // Ensure we only read .json files
-        if path.is_file() && path.extension().map_or(false, |s| s == "json") {
-            let filename = path.file_name().unwrap().to_string_lossy();
-            println!("  - Loading rules from {:?}", filename);
-
-            let json_data = match fs::read_to_string(&path) {
-                Ok(data) => data,
-                Err(e) => {
-                    eprintln!("    - Error: Failed to read policy file {:?}: {}", filename, e);
-                    continue;
-                }
-            };
-
-            if let Err(e) = engine.add_policies_from_json(&json_data) {
-                // Print error to stderr and skip this file
-                eprintln!("    - Error: Failed to parse policy file {:?}: {}", filename, e);
-                continue
-            }
-        }
-    }
+    engine.add_policies_from_dir(policies_path)?;
 
     let meta_file = Path::new(&args.metadata);
-    let filename = meta_file.file_name().unwrap().to_string_lossy();
     println!("Validating {}", &args.metadata);
-    let metadata_file_str = match fs::read_to_string(meta_file) {
-        Ok(data) => data,
-        Err(e) => {
-            panic!("Error: Failed to read metadata file {:?}: {}", filename, e);
-        }
-    };
 
-    let metadata_file_parsed: Value = match serde_json::from_str(&metadata_file_str) {
-        Ok(data) => data,
-        Err(e) => {
-            panic!("Error: Failed to parse metadata file {:?}: {}", filename, e);
-        }
-    };
-    
-    let metadata_file_result = engine.validate(&metadata_file_parsed);
-    
+    let metadata_file_str = fs::read_to_string(meta_file)
+        .map_err(|source| PolicyError::Io { path: meta_file.to_path_buf(), source })?;
+
+    let metadata_file_parsed: Value = serde_json::from_str(&metadata_file_str)
+        .map_err(|source| PolicyError::InvalidMetadata { path: args.metadata.clone(), source })?;
+
+    let metadata_file_result = engine.validate(&metadata_file_parsed)?;
+
     match &args.output {
         Some(output_file) => {
-            let json_report = metadata_file_result.to_json()?;
-            fs::write(&output_file, json_report)?;
-            
+            let json_report = metadata_file_result
+                .to_json()
+                .map_err(|source| PolicyError::Write { path: output_file.clone(), source })?;
+            fs::write(output_file, json_report)
+                .map_err(|source| PolicyError::Io { path: PathBuf::from(output_file), source })?;
+
             println!("Wrote JSON report to {:?}", output_file);
-        },
-        _ => println!("{}", metadata_file_result),
+        }
+        None => println!("{}", metadata_file_result),
     }
 
     Ok(())
-}
\ No newline at end of file
+}
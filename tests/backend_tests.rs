@@ -0,0 +1,71 @@
+use serde_json::Value;
+
+use policy_engine::{Check, JsonCheckBackend, JsonControl, PolicyBackend, PolicyEngine, PolicyError, Validation};
+
+/// A stub backend that always approves, regardless of the loaded controls.
+/// Exercises `PolicyEngine::with_backend` as an external evaluator would.
+struct AlwaysPassBackend;
+
+impl PolicyBackend for AlwaysPassBackend {
+    fn evaluate(&self, _controls: &[JsonControl], _metadata: &Value) -> Result<Validation, PolicyError> {
+        Ok(Validation::True)
+    }
+}
+
+/// A stub backend that always fails to evaluate, as an external backend
+/// would if e.g. the process it shells out to couldn't be started.
+struct AlwaysErrorBackend;
+
+impl PolicyBackend for AlwaysErrorBackend {
+    fn evaluate(&self, _controls: &[JsonControl], _metadata: &Value) -> Result<Validation, PolicyError> {
+        Err(PolicyError::Backend { message: "evaluator process failed to start".to_string() })
+    }
+}
+
+#[test]
+fn test_hand_built_matches_check_with_invalid_pattern_fails_instead_of_panicking() {
+    // `Check` and `JsonControl`'s fields are public specifically so external
+    // backends can build or inspect the tree directly, bypassing the
+    // deserializer/DSL validation that normally rejects bad regex patterns
+    // up front. `JsonCheckBackend` must not panic on such a control.
+    let controls = vec![JsonControl {
+        id: "hand_built_001".to_string(),
+        description: "built directly against the public Check type".to_string(),
+        check: Check::Matches { field: "/image/tag".to_string(), pattern: "(".to_string() },
+    }];
+
+    let metadata: Value = serde_json::from_str(r#"{"image": {"tag": "release"}}"#).unwrap();
+    let result = JsonCheckBackend.evaluate(&controls, &metadata).expect("evaluation should not fail");
+
+    match result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "hand_built_001"),
+        Validation::True => panic!("Expected the invalid-pattern control to fail"),
+    }
+}
+
+#[test]
+fn test_custom_backend_overrides_default_evaluation() {
+    let policy = r#"{
+        "controls": [
+            { "id": "backend_001", "description": "always fails by default", "check": { "op": "some", "field": "/missing" } }
+        ]
+    }"#;
+
+    let mut engine = PolicyEngine::with_backend(AlwaysPassBackend);
+    engine.add_policies_from_json("backend.json", policy).expect("policy should load");
+
+    let metadata: Value = serde_json::from_str("{}").unwrap();
+    let result = engine.validate(&metadata).expect("validation should not fail");
+
+    assert!(matches!(result, Validation::True));
+}
+
+#[test]
+fn test_custom_backend_evaluation_error_surfaces_to_caller() {
+    let engine = PolicyEngine::with_backend(AlwaysErrorBackend);
+    let metadata: Value = serde_json::from_str("{}").unwrap();
+
+    let result = engine.validate(&metadata);
+
+    assert!(matches!(result, Err(PolicyError::Backend { .. })));
+}
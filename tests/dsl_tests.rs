@@ -0,0 +1,49 @@
+use policy_engine::parse_policy_dsl;
+
+#[test]
+fn test_parse_simple_control_succeeds() {
+    let source = r#"
+        control pkg_signed "image must be signed" {
+            exists(/image/signature) and /image/tag == "release"
+        }
+    "#;
+
+    let controls = parse_policy_dsl(source).expect("expected the policy to parse");
+    assert_eq!(controls.len(), 1);
+}
+
+#[test]
+fn test_parse_multiple_controls_and_operators() {
+    let source = r#"
+        control replicas_ok "replica count must be sane" {
+            /replicas >= 2 and /replicas <= 10
+        }
+
+        control tag_ok "tag must look like a release" {
+            if /image/tag matches "^\d+\.\d+\.\d+$" then not some(/image/draft) else exists(/image/override)
+        }
+    "#;
+
+    let controls = parse_policy_dsl(source).expect("expected the policy to parse");
+    assert_eq!(controls.len(), 2);
+}
+
+#[test]
+fn test_parse_reports_span_on_malformed_control() {
+    let source = "control missing_brace \"oops\"\n    exists(/image/tag)\n}";
+
+    let error = parse_policy_dsl(source).expect_err("expected a parse error");
+    assert_eq!(error.line, 2);
+}
+
+#[test]
+fn test_parse_rejects_invalid_regex() {
+    let source = r#"
+        control bad_regex "malformed pattern" {
+            /image/tag matches "("
+        }
+    "#;
+
+    let error = parse_policy_dsl(source).expect_err("expected the malformed regex to be rejected");
+    assert!(error.message.contains("regex"));
+}
@@ -19,10 +19,10 @@ fn run_test(policy_file: &str, metadata_file: &str) -> Validation {
     let metadata_json = load_test_file(metadata_file);
     
     let mut engine = PolicyEngine::new();
-    engine.add_policies_from_json(&policy_json).expect("Failed to parse policy");
-    
+    engine.add_policies_from_json(policy_file, &policy_json).expect("Failed to parse policy");
+
     let metadata: Value = serde_json::from_str(&metadata_json).expect("Failed to parse metadata");
-    engine.validate(&metadata)
+    engine.validate(&metadata).expect("Failed to validate metadata")
 }
 
 #[test]
@@ -175,3 +175,125 @@ fn test_op_some() {
         _ => panic!("Test should have failed"),
     }
 }
+
+#[test]
+fn test_op_starts_with() {
+    let pass_result = run_test("policies/policy_starts_with.json", "metadata/meta_starts_with_pass.json");
+    match pass_result {
+        Validation::True => (),
+        _ => panic!("Test should have passed with Validation::True.")
+    }
+
+    let fail_result = run_test("policies/policy_starts_with.json", "metadata/meta_starts_with_fail.json");
+    match fail_result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "starts_with_001"),
+        _ => panic!("Test should have failed"),
+    }
+}
+
+#[test]
+fn test_op_ends_with() {
+    let pass_result = run_test("policies/policy_ends_with.json", "metadata/meta_ends_with_pass.json");
+    match pass_result {
+        Validation::True => (),
+        _ => panic!("Test should have passed with Validation::True.")
+    }
+
+    let fail_result = run_test("policies/policy_ends_with.json", "metadata/meta_ends_with_fail.json");
+    match fail_result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "ends_with_001"),
+        _ => panic!("Test should have failed"),
+    }
+}
+
+#[test]
+fn test_op_matches() {
+    let pass_result = run_test("policies/policy_matches.json", "metadata/meta_matches_pass.json");
+    match pass_result {
+        Validation::True => (),
+        _ => panic!("Test should have passed with Validation::True.")
+    }
+
+    let fail_result = run_test("policies/policy_matches.json", "metadata/meta_matches_fail.json");
+    match fail_result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "matches_001"),
+        _ => panic!("Test should have failed"),
+    }
+}
+
+#[test]
+fn test_op_greater_than() {
+    let pass_result = run_test("policies/policy_greater_than.json", "metadata/meta_greater_than_pass.json");
+    match pass_result {
+        Validation::True => (),
+        _ => panic!("Test should have passed with Validation::True.")
+    }
+
+    let fail_result = run_test("policies/policy_greater_than.json", "metadata/meta_greater_than_fail.json");
+    match fail_result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "greater_than_001"),
+        _ => panic!("Test should have failed"),
+    }
+}
+
+#[test]
+fn test_op_less_than() {
+    let pass_result = run_test("policies/policy_less_than.json", "metadata/meta_less_than_pass.json");
+    match pass_result {
+        Validation::True => (),
+        _ => panic!("Test should have passed with Validation::True.")
+    }
+
+    let fail_result = run_test("policies/policy_less_than.json", "metadata/meta_less_than_fail.json");
+    match fail_result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "less_than_001"),
+        _ => panic!("Test should have failed"),
+    }
+}
+
+#[test]
+fn test_op_greater_or_equal() {
+    let pass_result = run_test("policies/policy_greater_or_equal.json", "metadata/meta_greater_or_equal_pass.json");
+    match pass_result {
+        Validation::True => (),
+        _ => panic!("Test should have passed with Validation::True.")
+    }
+
+    let fail_result = run_test("policies/policy_greater_or_equal.json", "metadata/meta_greater_or_equal_fail.json");
+    match fail_result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "greater_or_equal_001"),
+        _ => panic!("Test should have failed"),
+    }
+}
+
+#[test]
+fn test_op_less_or_equal() {
+    let pass_result = run_test("policies/policy_less_or_equal.json", "metadata/meta_less_or_equal_pass.json");
+    match pass_result {
+        Validation::True => (),
+        _ => panic!("Test should have passed with Validation::True.")
+    }
+
+    let fail_result = run_test("policies/policy_less_or_equal.json", "metadata/meta_less_or_equal_fail.json");
+    match fail_result {
+        Validation::False(reqs) => assert_eq!(reqs[0].control, "less_or_equal_001"),
+        _ => panic!("Test should have failed"),
+    }
+}
+
+#[test]
+fn test_invalid_regex_pattern_rejected_at_parse_time() {
+    let bad_policy = r#"{
+        "controls": [
+            {
+                "id": "matches_bad_001",
+                "description": "malformed regex should fail to load",
+                "check": { "op": "matches", "field": "/image/tag", "pattern": "(" }
+            }
+        ]
+    }"#;
+
+    let mut engine = PolicyEngine::new();
+    let result = engine.add_policies_from_json("matches_bad.json", bad_policy);
+    assert!(result.is_err(), "Expected malformed regex pattern to be rejected at parse time");
+}
@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+
+use policy_engine::{PolicyEngine, PolicyError};
+
+fn test_cases_dir() -> PathBuf {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("tests/test_cases");
+    d
+}
+
+#[test]
+fn test_duplicate_control_id_rejected() {
+    let policy = r#"{
+        "controls": [
+            { "id": "dup_001", "description": "first", "check": { "op": "some", "field": "/a" } },
+            { "id": "dup_001", "description": "second", "check": { "op": "some", "field": "/b" } }
+        ]
+    }"#;
+
+    let mut engine = PolicyEngine::new();
+    let result = engine.add_policies_from_json("dup.json", policy);
+
+    match result {
+        Err(PolicyError::DuplicateControlId { id }) => assert_eq!(id, "dup_001"),
+        other => panic!("Expected PolicyError::DuplicateControlId, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_duplicate_control_id_rejected_across_files() {
+    let first = r#"{ "controls": [ { "id": "dup_002", "description": "first", "check": { "op": "some", "field": "/a" } } ] }"#;
+    let second = r#"{ "controls": [ { "id": "dup_002", "description": "second", "check": { "op": "some", "field": "/b" } } ] }"#;
+
+    let mut engine = PolicyEngine::new();
+    engine.add_policies_from_json("first.json", first).expect("first file should load");
+    let result = engine.add_policies_from_json("second.json", second);
+
+    match result {
+        Err(PolicyError::DuplicateControlId { id }) => assert_eq!(id, "dup_002"),
+        other => panic!("Expected PolicyError::DuplicateControlId, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_pointer_rejected() {
+    let policy = r#"{
+        "controls": [
+            { "id": "bad_pointer_001", "description": "malformed field", "check": { "op": "some", "field": "image/tag" } }
+        ]
+    }"#;
+
+    let mut engine = PolicyEngine::new();
+    let result = engine.add_policies_from_json("bad_pointer.json", policy);
+
+    match result {
+        Err(PolicyError::InvalidPointer { control, field }) => {
+            assert_eq!(control, "bad_pointer_001");
+            assert_eq!(field, "image/tag");
+        }
+        other => panic!("Expected PolicyError::InvalidPointer, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_add_policies_from_dir_loads_every_file() {
+    let mut engine = PolicyEngine::new();
+    engine
+        .add_policies_from_dir(test_cases_dir().join("policy_dir"))
+        .expect("directory of valid policies should load");
+
+    let metadata: serde_json::Value = serde_json::from_str(r#"{"image": {"tag": "release"}}"#).unwrap();
+    let result = engine.validate(&metadata).expect("validation should not fail");
+
+    match result {
+        policy_engine::Validation::False(reqs) => {
+            assert_eq!(reqs.len(), 1);
+            assert_eq!(reqs[0].control, "dir_b_001");
+        }
+        policy_engine::Validation::True => panic!("Expected the registry control to fail"),
+    }
+}
+
+#[test]
+fn test_add_policies_from_dir_skips_malformed_file_and_loads_the_rest() {
+    let mut engine = PolicyEngine::new();
+    engine
+        .add_policies_from_dir(test_cases_dir().join("policy_dir_with_bad_file"))
+        .expect("a malformed file should be skipped, not fail the whole directory");
+
+    let metadata: serde_json::Value = serde_json::from_str(r#"{"image": {"tag": ""}}"#).unwrap();
+    let result = engine.validate(&metadata).expect("validation should not fail");
+
+    match result {
+        policy_engine::Validation::False(reqs) => {
+            assert_eq!(reqs.len(), 1);
+            assert_eq!(reqs[0].control, "good_001");
+        }
+        policy_engine::Validation::True => panic!("Expected the control from the valid file to fail"),
+    }
+}
+
+#[test]
+fn test_add_policies_from_dir_ignores_extensionless_files() {
+    let mut engine = PolicyEngine::new();
+    engine
+        .add_policies_from_dir(test_cases_dir().join("policy_dir_with_non_json"))
+        .expect("extensionless files should be ignored, not treated as policies");
+
+    let metadata: serde_json::Value = serde_json::from_str(r#"{"image": {"tag": ""}}"#).unwrap();
+    let result = engine.validate(&metadata).expect("validation should not fail");
+
+    match result {
+        policy_engine::Validation::False(reqs) => {
+            assert_eq!(reqs.len(), 1);
+            assert_eq!(reqs[0].control, "non_json_001");
+        }
+        policy_engine::Validation::True => panic!("Expected the control from good.json to fail"),
+    }
+}
+
+#[test]
+fn test_add_policies_from_dir_reports_missing_directory() {
+    let mut engine = PolicyEngine::new();
+    let result = engine.add_policies_from_dir(test_cases_dir().join("does_not_exist"));
+
+    assert!(matches!(result, Err(PolicyError::Io { .. })));
+}